@@ -0,0 +1,55 @@
+//! Pluggable time source for [`crate::RtTimer`].
+//!
+//! Everything in this crate that needs to know "what time is it" goes
+//! through a free-running tick counter rather than `std::time::Instant`
+//! directly, so the same PID/timer/stats code can run unmodified on a
+//! Cortex-M target (backed by a SysTick or TIM peripheral) and on a Linux
+//! host (backed by [`StdClock`]).
+
+/// Source of monotonic time ticks.
+///
+/// Implementations must return a value that never decreases between
+/// calls (wraparound aside) and must report the rate it counts at via
+/// [`Clock::TICK_HZ`]. `RtTimer` converts tick deltas to microseconds
+/// using this rate, so a single `u64` tick count is all an implementation
+/// needs to provide.
+pub trait Clock {
+    /// Ticks per second for this clock.
+    const TICK_HZ: u64;
+
+    /// Current tick count since an arbitrary epoch.
+    fn now_ticks(&self) -> u64;
+}
+
+/// [`Clock`] backed by `std::time::Instant`, available on hosts with the
+/// standard library.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StdClock;
+
+#[cfg(feature = "std")]
+impl Clock for StdClock {
+    const TICK_HZ: u64 = 1_000_000_000;
+
+    fn now_ticks(&self) -> u64 {
+        use std::sync::OnceLock;
+        use std::time::Instant;
+
+        static EPOCH: OnceLock<Instant> = OnceLock::new();
+        let epoch = *EPOCH.get_or_init(Instant::now);
+        Instant::now().duration_since(epoch).as_nanos() as u64
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn std_clock_is_monotonic() {
+        let clock = StdClock;
+        let a = clock.now_ticks();
+        let b = clock.now_ticks();
+        assert!(b >= a);
+    }
+}
@@ -0,0 +1,156 @@
+//! Rolling diagnostics window for [`crate::RtTimer`].
+//!
+//! `RtStats` only tracks lifetime aggregates (min/max/avg, percentiles,
+//! jitter), so once a deadline miss happens there's no way to see the
+//! history that led up to it. This
+//! module keeps a small fixed-size ring of recent cycle snapshots and a
+//! separate ring of recent deadline-miss events, mirroring how mature
+//! timekeeping diagnostics retain a rolling window of state rather than
+//! only lifetime aggregates.
+
+/// A single cycle's latency and jitter, tagged with a monotonically
+/// increasing cycle index.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CycleSample {
+    pub cycle_index: u64,
+    pub latency_us: u64,
+    /// `latency_us - period_us`: positive means the cycle ran long.
+    pub jitter_us: i64,
+}
+
+/// A deadline miss, with the amount by which the cycle overran.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DeadlineMiss {
+    pub cycle_index: u64,
+    pub overrun_us: u64,
+}
+
+/// Fixed-capacity circular buffer of the last `N` items of `T`.
+pub(crate) struct Ring<T, const N: usize> {
+    buf: [Option<T>; N],
+    /// Index the next `push` will write to.
+    head: usize,
+    len: usize,
+}
+
+impl<T: Copy, const N: usize> Ring<T, N> {
+    pub(crate) const fn new() -> Self {
+        Self {
+            buf: [None; N],
+            head: 0,
+            len: 0,
+        }
+    }
+
+    pub(crate) fn push(&mut self, item: T) {
+        if N == 0 {
+            return;
+        }
+        self.buf[self.head] = Some(item);
+        self.head = (self.head + 1) % N;
+        if self.len < N {
+            self.len += 1;
+        }
+    }
+
+    /// A snapshot of the ring's contents, oldest first.
+    pub(crate) fn snapshot(&self) -> RingIter<T, N> {
+        let mut items = [None; N];
+        let start = if self.len < N { 0 } else { self.head };
+        for (i, slot) in items.iter_mut().enumerate().take(self.len) {
+            *slot = self.buf[(start + i) % N];
+        }
+        RingIter {
+            items,
+            idx: 0,
+            len: self.len,
+        }
+    }
+}
+
+/// Owned iterator over a [`Ring`] snapshot, oldest entry first.
+pub struct RingIter<T, const N: usize> {
+    items: [Option<T>; N],
+    idx: usize,
+    len: usize,
+}
+
+impl<T: Copy, const N: usize> Iterator for RingIter<T, N> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.idx >= self.len {
+            return None;
+        }
+        let item = self.items[self.idx];
+        self.idx += 1;
+        item
+    }
+}
+
+/// Recent-history diagnostics retained alongside [`crate::RtStats`].
+pub(crate) struct Diagnostics<const N: usize> {
+    samples: Ring<CycleSample, N>,
+    misses: Ring<DeadlineMiss, N>,
+}
+
+impl<const N: usize> Diagnostics<N> {
+    pub(crate) const fn new() -> Self {
+        Self {
+            samples: Ring::new(),
+            misses: Ring::new(),
+        }
+    }
+
+    pub(crate) fn record_cycle(&mut self, cycle_index: u64, latency_us: u64, period_us: u64) {
+        self.samples.push(CycleSample {
+            cycle_index,
+            latency_us,
+            jitter_us: latency_us as i64 - period_us as i64,
+        });
+
+        if latency_us > period_us {
+            self.misses.push(DeadlineMiss {
+                cycle_index,
+                overrun_us: latency_us - period_us,
+            });
+        }
+    }
+
+    pub(crate) fn recent_samples(&self) -> RingIter<CycleSample, N> {
+        self.samples.snapshot()
+    }
+
+    pub(crate) fn recent_misses(&self) -> RingIter<DeadlineMiss, N> {
+        self.misses.snapshot()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ring_retains_only_last_n_and_preserves_order() {
+        let mut ring: Ring<u64, 3> = Ring::new();
+        for i in 0..5u64 {
+            ring.push(i);
+        }
+        assert!(ring.snapshot().eq([2u64, 3, 4]));
+    }
+
+    #[test]
+    fn diagnostics_tracks_misses_separately() {
+        let mut diag: Diagnostics<4> = Diagnostics::new();
+        diag.record_cycle(0, 100, 1000);
+        diag.record_cycle(1, 2000, 1000);
+        diag.record_cycle(2, 900, 1000);
+
+        assert_eq!(diag.recent_samples().count(), 3);
+        let mut misses = diag.recent_misses();
+        let miss = misses.next().expect("one miss recorded");
+        assert_eq!(miss.cycle_index, 1);
+        assert_eq!(miss.overrun_us, 1000);
+        assert!(misses.next().is_none());
+    }
+}
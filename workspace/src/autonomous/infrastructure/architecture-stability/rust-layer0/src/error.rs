@@ -0,0 +1,25 @@
+//! Error types for Layer 0 runtime operations.
+
+use thiserror::Error;
+
+/// Error types for Layer 0 runtime operations.
+///
+/// Message payloads are `&'static str` rather than an owned `String` so
+/// this type has no dependency on `alloc`, which keeps it usable on
+/// targets with no heap.
+#[derive(Error, Debug)]
+pub enum Layer0Error {
+    #[error("Hardware initialization failed: {0}")]
+    HardwareInitFailed(&'static str),
+
+    #[error("Real-time constraint violated: expected {expected_us}μs, got {actual_us}μs")]
+    RealTimeViolation { expected_us: u64, actual_us: u64 },
+
+    #[error("Sensor data unavailable")]
+    SensorDataUnavailable,
+
+    #[error("Control loop error: {0}")]
+    ControlLoopError(&'static str),
+}
+
+pub type Result<T> = core::result::Result<T, Layer0Error>;
@@ -0,0 +1,346 @@
+//! Closing the control loop against `embedded-hal`.
+//!
+//! [`SensorProducer`] samples a value each cycle into a [`SensorBuffer`];
+//! [`PwmActuator`] maps a [`PidController`] output onto a PWM channel;
+//! [`ControlLoopBuilder`] wires sensor -> PID -> actuator around an
+//! [`RtTimer`] so a cycle is just `run_once()`.
+
+use core::marker::PhantomData;
+
+use embedded_hal::pwm::SetDutyCycle;
+
+use crate::clock::Clock;
+use crate::error::{Layer0Error, Result};
+use crate::math::round;
+use crate::pid::PidController;
+use crate::sensor::SensorBuffer;
+use crate::timer::RtTimer;
+
+/// Samples a value each cycle and writes it into a [`SensorBuffer`],
+/// timestamped in microseconds since the cycle's timer started.
+///
+/// `S` is typically a closure wrapping an ADC read (`embedded-hal` 1.0,
+/// which this crate builds against, has no ADC trait; the 0.2-era
+/// `adc::OneShot::read` is `nb`-based and doesn't fit a plain trait
+/// bound here either), but any `FnMut() -> Option<T>`
+/// works, including a mock for tests. Returning `None` means no fresh
+/// reading was available this cycle (e.g. the ADC wasn't ready yet); the
+/// buffer is left untouched, so its timestamp ages and a subsequent
+/// [`ControlLoop::run_once`] can detect the stale data via
+/// `max_sample_age_us`.
+pub struct SensorProducer<T: Copy + Default, S: FnMut() -> Option<T>> {
+    buffer: SensorBuffer<T>,
+    sample: S,
+}
+
+impl<T: Copy + Default, S: FnMut() -> Option<T>> SensorProducer<T, S> {
+    pub fn new(sample: S) -> Self {
+        Self {
+            buffer: SensorBuffer::new(),
+            sample,
+        }
+    }
+
+    /// Sample once, storing the result timestamped `timestamp_us` if one
+    /// was available. Leaves the buffer (and its timestamp) unchanged
+    /// otherwise.
+    pub fn sample_into(&mut self, timestamp_us: u64) {
+        if let Some(value) = (self.sample)() {
+            self.buffer.write(value, timestamp_us);
+        }
+    }
+
+    pub fn buffer(&self) -> &SensorBuffer<T> {
+        &self.buffer
+    }
+}
+
+/// Maps a [`PidController`] output onto an `embedded-hal`
+/// `SetDutyCycle` PWM channel, clamping to `[output_min, output_max]`
+/// and scaling onto the channel's duty-cycle range.
+pub struct PwmActuator<P: SetDutyCycle> {
+    pwm: P,
+    output_min: f64,
+    output_max: f64,
+}
+
+impl<P: SetDutyCycle> PwmActuator<P> {
+    /// `output_min`/`output_max` describe the PID output range that maps
+    /// onto the channel's full duty-cycle span.
+    pub fn new(pwm: P, output_min: f64, output_max: f64) -> Self {
+        Self {
+            pwm,
+            output_min,
+            output_max,
+        }
+    }
+
+    /// Clamp, scale, and apply `output` to the PWM channel.
+    pub fn apply(&mut self, output: f64) -> core::result::Result<(), P::Error> {
+        let clamped = output.clamp(self.output_min, self.output_max);
+        let span = (self.output_max - self.output_min).max(f64::EPSILON);
+        let fraction = (clamped - self.output_min) / span;
+        let max_duty = self.pwm.max_duty_cycle() as f64;
+        let duty = round(fraction * max_duty) as u16;
+        self.pwm.set_duty_cycle(duty)
+    }
+}
+
+/// Builds a [`ControlLoop`] from a clock, a sensor sampler, a tuned
+/// [`PidController`], and a PWM actuator.
+pub struct ControlLoopBuilder<C: Clock, T: Copy + Default, S: FnMut() -> Option<T>, P: SetDutyCycle> {
+    clock: C,
+    frequency_hz: u32,
+    sensor: S,
+    pid: PidController,
+    actuator: PwmActuator<P>,
+    setpoint: f64,
+    max_sample_age_us: u64,
+    _value: PhantomData<T>,
+}
+
+impl<C: Clock, T: Copy + Default, S: FnMut() -> Option<T>, P: SetDutyCycle>
+    ControlLoopBuilder<C, T, S, P>
+{
+    pub fn new(
+        clock: C,
+        frequency_hz: u32,
+        sensor: S,
+        pid: PidController,
+        actuator: PwmActuator<P>,
+    ) -> Self {
+        Self {
+            clock,
+            frequency_hz,
+            sensor,
+            pid,
+            actuator,
+            setpoint: 0.0,
+            max_sample_age_us: u64::MAX,
+            _value: PhantomData,
+        }
+    }
+
+    pub fn setpoint(mut self, setpoint: f64) -> Self {
+        self.setpoint = setpoint;
+        self
+    }
+
+    /// Reject a sensor sample older than `max_age_us` relative to the
+    /// current cycle, returning [`Layer0Error::SensorDataUnavailable`].
+    pub fn max_sample_age_us(mut self, max_age_us: u64) -> Self {
+        self.max_sample_age_us = max_age_us;
+        self
+    }
+
+    pub fn build(self) -> ControlLoop<C, T, S, P> {
+        let timer = RtTimer::with_clock(self.frequency_hz, self.clock);
+        ControlLoop {
+            timer,
+            sensor: SensorProducer::new(self.sensor),
+            pid: self.pid,
+            actuator: self.actuator,
+            setpoint: self.setpoint,
+            dt_seconds: 1.0 / self.frequency_hz as f64,
+            max_sample_age_us: self.max_sample_age_us,
+        }
+    }
+}
+
+/// A sensor -> PID -> actuator control loop, paced by an [`RtTimer`].
+pub struct ControlLoop<C: Clock, T: Copy + Default, S: FnMut() -> Option<T>, P: SetDutyCycle> {
+    timer: RtTimer<C>,
+    sensor: SensorProducer<T, S>,
+    pid: PidController,
+    actuator: PwmActuator<P>,
+    setpoint: f64,
+    dt_seconds: f64,
+    max_sample_age_us: u64,
+}
+
+impl<C: Clock, T: Copy + Default + Into<f64>, S: FnMut() -> Option<T>, P: SetDutyCycle>
+    ControlLoop<C, T, S, P>
+{
+    pub fn set_setpoint(&mut self, setpoint: f64) {
+        self.setpoint = setpoint;
+    }
+
+    pub fn timer(&self) -> &RtTimer<C> {
+        &self.timer
+    }
+
+    /// Run one sense -> compute -> actuate iteration, paced by
+    /// [`RtTimer::wait_next_cycle`].
+    pub fn run_once(&mut self) -> Result<()> {
+        self.timer.wait_next_cycle()?;
+
+        let timestamp_us = self.timer.elapsed_us();
+        self.sensor.sample_into(timestamp_us);
+
+        let (measured, sample_timestamp_us) = self
+            .sensor
+            .buffer()
+            .read()
+            .ok_or(Layer0Error::SensorDataUnavailable)?;
+
+        if timestamp_us.saturating_sub(sample_timestamp_us) > self.max_sample_age_us {
+            return Err(Layer0Error::SensorDataUnavailable);
+        }
+
+        let output = self
+            .pid
+            .compute(self.setpoint, measured.into(), self.dt_seconds);
+
+        self.actuator
+            .apply(output)
+            .map_err(|_| Layer0Error::ControlLoopError("actuator write failed"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::cell::Cell;
+
+    /// Deterministic [`Clock`] for tests: each read advances by a fixed
+    /// `step`, so `RtTimer::wait_next_cycle`'s busy-wait terminates
+    /// without needing real time to pass.
+    struct MockClock {
+        ticks: Cell<u64>,
+        step: u64,
+    }
+
+    impl MockClock {
+        fn new(step: u64) -> Self {
+            Self {
+                ticks: Cell::new(0),
+                step,
+            }
+        }
+    }
+
+    impl Clock for MockClock {
+        const TICK_HZ: u64 = 1_000_000;
+
+        fn now_ticks(&self) -> u64 {
+            let t = self.ticks.get();
+            self.ticks.set(t + self.step);
+            t
+        }
+    }
+
+    #[derive(Debug)]
+    struct MockPwmError;
+
+    impl embedded_hal::pwm::Error for MockPwmError {
+        fn kind(&self) -> embedded_hal::pwm::ErrorKind {
+            embedded_hal::pwm::ErrorKind::Other
+        }
+    }
+
+    struct MockPwm {
+        max_duty: u16,
+        last_duty: Option<u16>,
+    }
+
+    impl MockPwm {
+        fn new(max_duty: u16) -> Self {
+            Self {
+                max_duty,
+                last_duty: None,
+            }
+        }
+    }
+
+    impl embedded_hal::pwm::ErrorType for MockPwm {
+        type Error = MockPwmError;
+    }
+
+    impl SetDutyCycle for MockPwm {
+        fn max_duty_cycle(&self) -> u16 {
+            self.max_duty
+        }
+
+        fn set_duty_cycle(&mut self, duty: u16) -> core::result::Result<(), Self::Error> {
+            self.last_duty = Some(duty);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_sensor_producer_leaves_buffer_stale_when_sample_is_none() {
+        let mut producer = SensorProducer::<f64, _>::new(|| None);
+        producer.sample_into(1000);
+        assert!(producer.buffer().read().is_none());
+    }
+
+    #[test]
+    fn test_sensor_producer_writes_fresh_samples() {
+        let mut producer = SensorProducer::new(|| Some(42.0));
+        producer.sample_into(1000);
+        let (value, ts) = producer.buffer().read().unwrap();
+        assert_eq!(value, 42.0);
+        assert_eq!(ts, 1000);
+    }
+
+    #[test]
+    fn test_pwm_actuator_scales_output_onto_duty_range() {
+        let mut actuator = PwmActuator::new(MockPwm::new(1000), -10.0, 10.0);
+
+        actuator.apply(0.0).unwrap();
+        assert_eq!(actuator.pwm.last_duty, Some(500));
+
+        actuator.apply(10.0).unwrap();
+        assert_eq!(actuator.pwm.last_duty, Some(1000));
+
+        actuator.apply(-20.0).unwrap(); // clamped to output_min
+        assert_eq!(actuator.pwm.last_duty, Some(0));
+    }
+
+    fn test_loop_parts(max_duty: u16) -> (MockClock, PidController, PwmActuator<MockPwm>) {
+        (
+            MockClock::new(50),
+            PidController::new(1.0, 0.0, 0.0, 100.0),
+            PwmActuator::new(MockPwm::new(max_duty), 0.0, 100.0),
+        )
+    }
+
+    #[test]
+    fn test_control_loop_runs_a_cycle() {
+        let (clock, pid, actuator) = test_loop_parts(1000);
+        let mut loop_ = ControlLoopBuilder::new(clock, 1000, || Some(5.0), pid, actuator)
+            .setpoint(10.0)
+            .build();
+
+        assert!(loop_.run_once().is_ok());
+    }
+
+    #[test]
+    fn test_control_loop_rejects_stale_sensor_data() {
+        let (clock, pid, actuator) = test_loop_parts(1000);
+
+        // The first cycle samples successfully, giving the buffer a
+        // baseline timestamp; the second cycle's sensor has no fresh
+        // reading, so by the time it's read the buffer is a full cycle
+        // stale.
+        let mut sampled_once = false;
+        let sensor = move || {
+            if sampled_once {
+                None
+            } else {
+                sampled_once = true;
+                Some(5.0)
+            }
+        };
+
+        let mut loop_ = ControlLoopBuilder::new(clock, 1000, sensor, pid, actuator)
+            .max_sample_age_us(0)
+            .build();
+
+        assert!(loop_.run_once().is_ok());
+        assert!(matches!(
+            loop_.run_once(),
+            Err(Layer0Error::SensorDataUnavailable)
+        ));
+    }
+}
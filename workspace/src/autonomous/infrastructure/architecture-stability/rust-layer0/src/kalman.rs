@@ -0,0 +1,187 @@
+//! Kalman-filter sensor fusion, layered on top of [`SensorBuffer`] so a
+//! noisy sensor stream can be smoothed before a [`crate::PidController`]
+//! consumes it.
+
+use crate::lock::Guarded;
+use crate::math::sqrt;
+use crate::sensor::SensorBuffer;
+
+/// Scalar (1-D) Kalman filter.
+///
+/// Implements the standard recurrence: `predict` advances the error
+/// variance by the process noise scaled by elapsed time, and `update`
+/// folds in a measurement weighted by the Kalman gain.
+#[derive(Debug, Clone, Copy)]
+pub struct KalmanFilter1D {
+    x: f64,
+    p: f64,
+    q: f64,
+    r: f64,
+}
+
+impl KalmanFilter1D {
+    /// Build a filter with process noise `q` and measurement noise `r`.
+    pub fn new(q: f64, r: f64) -> Self {
+        Self { x: 0.0, p: 1.0, q, r }
+    }
+
+    /// Reset the estimate and its variance, e.g. on re-acquiring a target.
+    pub fn reset(&mut self, initial: f64, variance: f64) {
+        self.x = initial;
+        self.p = variance;
+    }
+
+    /// Advance the error variance by `dt` seconds of process noise.
+    pub fn predict(&mut self, dt: f64) {
+        self.p += self.q * dt;
+    }
+
+    /// Incorporate measurement `z`, weighted by the Kalman gain.
+    pub fn update(&mut self, z: f64) {
+        let k = self.p / (self.p + self.r);
+        self.x += k * (z - self.x);
+        self.p *= 1.0 - k;
+    }
+
+    /// Current estimate and its error variance.
+    pub fn value(&self) -> (f64, f64) {
+        (self.x, self.p)
+    }
+}
+
+/// A [`SensorBuffer`] fronted by a [`KalmanFilter1D`], smoothing raw
+/// samples before they're read by a control loop.
+///
+/// `T` is the scalar sensor reading type (e.g. `f64`), converted to and
+/// from `f64` for the filter math.
+pub struct FusedSensor<T: Copy + Default + Into<f64> + From<f64>> {
+    buffer: SensorBuffer<T>,
+    filter: Guarded<KalmanFilter1D>,
+    outlier_threshold_std: Option<f64>,
+}
+
+impl<T: Copy + Default + Into<f64> + From<f64>> FusedSensor<T> {
+    /// Build a fused sensor with process noise `q` and measurement noise
+    /// `r` for the underlying [`KalmanFilter1D`].
+    pub fn new(q: f64, r: f64) -> Self {
+        Self {
+            buffer: SensorBuffer::new(),
+            filter: Guarded::new(KalmanFilter1D::new(q, r)),
+            outlier_threshold_std: None,
+        }
+    }
+
+    /// Reject measurements whose innovation `|z - x|` exceeds `n_std`
+    /// standard deviations of the current estimate.
+    pub fn with_outlier_rejection(mut self, n_std: f64) -> Self {
+        self.outlier_threshold_std = Some(n_std);
+        self
+    }
+
+    /// Ingest a raw sample, gating on timestamp and optionally rejecting
+    /// outliers, and fold it into the Kalman estimate.
+    pub fn ingest(&self, sample: T, timestamp_us: u64) {
+        // Reuse the buffer's own validity/timestamp rather than tracking
+        // "do we have a sample yet, and since when" a second time.
+        let previous = self.buffer.read();
+        if let Some((_, last)) = previous {
+            if timestamp_us <= last {
+                return;
+            }
+        }
+
+        let z: f64 = sample.into();
+        let dt_us = timestamp_us.saturating_sub(previous.map_or(0, |(_, last)| last));
+
+        let accepted = self.filter.with(|f| {
+            // Nothing to predict from or gate the first sample against
+            // yet; accept it unconditionally so the estimate has
+            // somewhere to start.
+            if previous.is_some() {
+                f.predict(dt_us as f64 / 1_000_000.0);
+
+                if let Some(n_std) = self.outlier_threshold_std {
+                    let (estimate, variance) = f.value();
+                    let innovation = (z - estimate).abs();
+                    if variance > 0.0 && innovation > n_std * sqrt(variance) {
+                        return false;
+                    }
+                }
+            }
+
+            f.update(z);
+            true
+        });
+
+        if accepted {
+            self.buffer.write(sample, timestamp_us);
+        }
+    }
+
+    /// Current fused estimate and its variance, once at least one sample
+    /// has been accepted.
+    pub fn fused_value(&self) -> Option<(T, f64)> {
+        self.buffer.read()?;
+        let (estimate, variance) = self.filter.with(|f| f.value());
+        Some((T::from(estimate), variance))
+    }
+
+    /// The most recent accepted raw sample and its timestamp, bypassing
+    /// the filter. Stale and outlier-rejected samples are never stored.
+    pub fn raw(&self) -> Option<(T, u64)> {
+        self.buffer.read()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kalman_converges_to_constant_signal() {
+        let mut filter = KalmanFilter1D::new(0.001, 1.0);
+        filter.reset(0.0, 1.0);
+
+        for _ in 0..50 {
+            filter.predict(0.01);
+            filter.update(10.0);
+        }
+
+        let (estimate, _) = filter.value();
+        assert!((estimate - 10.0).abs() < 0.5);
+    }
+
+    #[test]
+    fn test_fused_sensor_accepts_first_sample_at_tick_zero() {
+        let fused = FusedSensor::<f64>::new(0.01, 1.0);
+        fused.ingest(1.0, 0);
+        fused.ingest(2.0, 1000);
+
+        let (_, ts) = fused.raw().unwrap();
+        assert_eq!(ts, 1000, "second sample must not be mistaken for the first");
+    }
+
+    #[test]
+    fn test_fused_sensor_rejects_stale_samples() {
+        let fused = FusedSensor::<f64>::new(0.01, 1.0);
+        fused.ingest(1.0, 1000);
+        fused.ingest(2.0, 500); // stale, should be ignored
+
+        let (_, ts) = fused.raw().unwrap();
+        assert_eq!(ts, 1000);
+    }
+
+    #[test]
+    fn test_fused_sensor_rejects_outliers() {
+        let fused = FusedSensor::<f64>::new(0.001, 0.01).with_outlier_rejection(3.0);
+        for i in 1..20 {
+            fused.ingest(10.0, i * 1000);
+        }
+
+        let (before, _) = fused.fused_value().unwrap();
+        fused.ingest(1_000.0, 21_000);
+        let (after, _) = fused.fused_value().unwrap();
+
+        assert!((after - before).abs() < 1.0, "outlier should be rejected");
+    }
+}
@@ -0,0 +1,34 @@
+//! Internal mutual-exclusion primitive that works the same way whether
+//! this crate is built with `std` (using `parking_lot`) or as `no_std`
+//! (using `critical-section`).
+//!
+//! Both backends are exposed through a single `with` method so the rest
+//! of the crate never has to branch on which one is active.
+
+#[cfg(feature = "std")]
+pub(crate) struct Guarded<T>(parking_lot::Mutex<T>);
+
+#[cfg(feature = "std")]
+impl<T> Guarded<T> {
+    pub(crate) const fn new(value: T) -> Self {
+        Self(parking_lot::Mutex::new(value))
+    }
+
+    pub(crate) fn with<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        f(&mut self.0.lock())
+    }
+}
+
+#[cfg(not(feature = "std"))]
+pub(crate) struct Guarded<T>(critical_section::Mutex<core::cell::RefCell<T>>);
+
+#[cfg(not(feature = "std"))]
+impl<T> Guarded<T> {
+    pub(crate) const fn new(value: T) -> Self {
+        Self(critical_section::Mutex::new(core::cell::RefCell::new(value)))
+    }
+
+    pub(crate) fn with<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        critical_section::with(|cs| f(&mut self.0.borrow(cs).borrow_mut()))
+    }
+}
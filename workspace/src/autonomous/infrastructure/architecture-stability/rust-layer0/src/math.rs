@@ -0,0 +1,40 @@
+//! Tiny math shims so the same call sites work with or without `std`.
+//!
+//! `f64::sqrt`/`f64::ceil` live in `std`, not `core`, so the `no_std`
+//! build pulls them from `libm` instead. Everything that needs them
+//! (Kalman innovation gating, latency jitter, percentile rounding) goes
+//! through here rather than branching on the feature itself.
+
+pub(crate) fn sqrt(x: f64) -> f64 {
+    #[cfg(feature = "std")]
+    {
+        x.sqrt()
+    }
+    #[cfg(not(feature = "std"))]
+    {
+        libm::sqrt(x)
+    }
+}
+
+pub(crate) fn ceil(x: f64) -> f64 {
+    #[cfg(feature = "std")]
+    {
+        x.ceil()
+    }
+    #[cfg(not(feature = "std"))]
+    {
+        libm::ceil(x)
+    }
+}
+
+#[cfg(feature = "hal")]
+pub(crate) fn round(x: f64) -> f64 {
+    #[cfg(feature = "std")]
+    {
+        x.round()
+    }
+    #[cfg(not(feature = "std"))]
+    {
+        libm::round(x)
+    }
+}
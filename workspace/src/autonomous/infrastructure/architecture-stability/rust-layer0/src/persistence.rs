@@ -0,0 +1,322 @@
+//! Persisting [`crate::PidController`] gains and loop frequency to NOR
+//! flash via `embedded-storage`, so field-deployed controllers keep their
+//! tuning across reboots without pulling in a filesystem.
+//!
+//! Records are fixed-layout, versioned, and CRC-checked, so a page that
+//! was only partially written (e.g. power loss mid-write) is detected
+//! and rejected on load rather than silently loading garbage gains.
+
+use embedded_storage::nor_flash::{NorFlash, ReadNorFlash};
+
+const MAGIC: u8 = 0xA5;
+const VERSION: u8 = 1;
+
+/// On-flash record length in bytes: 1 magic + 1 version + 2 reserved +
+/// 4 `f64` fields (32) + 1 `u32` frequency (4) + 1 `u32` CRC (4).
+pub const RECORD_LEN: usize = 44;
+
+/// Upper bound on the padded record length: `RECORD_LEN` rounded up to
+/// a multiple of the flash write granularity must not exceed this.
+const MAX_PADDED_LEN: usize = 64;
+
+/// PID gains and loop frequency, as persisted to flash.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PersistedConfig {
+    pub kp: f64,
+    pub ki: f64,
+    pub kd: f64,
+    pub output_limit: f64,
+    pub frequency_hz: u32,
+}
+
+impl PersistedConfig {
+    fn to_bytes(self) -> [u8; RECORD_LEN] {
+        let mut buf = [0u8; RECORD_LEN];
+        buf[0] = MAGIC;
+        buf[1] = VERSION;
+        buf[4..12].copy_from_slice(&self.kp.to_le_bytes());
+        buf[12..20].copy_from_slice(&self.ki.to_le_bytes());
+        buf[20..28].copy_from_slice(&self.kd.to_le_bytes());
+        buf[28..36].copy_from_slice(&self.output_limit.to_le_bytes());
+        buf[36..40].copy_from_slice(&self.frequency_hz.to_le_bytes());
+        let crc = crc32(&buf[..40]);
+        buf[40..44].copy_from_slice(&crc.to_le_bytes());
+        buf
+    }
+
+    fn from_bytes<E>(buf: &[u8; RECORD_LEN]) -> Result<Self, PersistenceError<E>> {
+        if buf[0] != MAGIC || buf[1] != VERSION {
+            return Err(PersistenceError::InvalidHeader);
+        }
+
+        let expected_crc = u32::from_le_bytes(buf[40..44].try_into().unwrap());
+        if crc32(&buf[..40]) != expected_crc {
+            return Err(PersistenceError::CrcMismatch);
+        }
+
+        Ok(Self {
+            kp: f64::from_le_bytes(buf[4..12].try_into().unwrap()),
+            ki: f64::from_le_bytes(buf[12..20].try_into().unwrap()),
+            kd: f64::from_le_bytes(buf[20..28].try_into().unwrap()),
+            output_limit: f64::from_le_bytes(buf[28..36].try_into().unwrap()),
+            frequency_hz: u32::from_le_bytes(buf[36..40].try_into().unwrap()),
+        })
+    }
+}
+
+/// Errors from saving or loading a [`PersistedConfig`].
+#[derive(Debug)]
+pub enum PersistenceError<E> {
+    /// The record's magic byte or version didn't match — the page was
+    /// never written, or was written by an incompatible version.
+    InvalidHeader,
+    /// The record's CRC didn't match its contents — most likely a page
+    /// that was only partially written before a reset or power loss.
+    CrcMismatch,
+    /// `F::WRITE_SIZE` is large enough that padding a record out to a
+    /// multiple of it would exceed the padded length this module
+    /// supports.
+    UnsupportedWriteGranularity,
+    /// The underlying flash read/write/erase failed.
+    Flash(E),
+}
+
+impl<E: core::fmt::Debug> core::fmt::Display for PersistenceError<E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::InvalidHeader => write!(f, "invalid or missing record header"),
+            Self::CrcMismatch => write!(f, "CRC mismatch (partially written record)"),
+            Self::UnsupportedWriteGranularity => {
+                write!(f, "flash write granularity too large for a padded record")
+            }
+            Self::Flash(e) => write!(f, "flash I/O error: {e:?}"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E: core::fmt::Debug> std::error::Error for PersistenceError<E> {}
+
+/// Write `config` to `flash` at `offset`, erasing the covering region
+/// first and padding the record out to the flash's minimum write
+/// granularity. Fails with [`PersistenceError::UnsupportedWriteGranularity`]
+/// rather than silently writing a mis-aligned record if `F::WRITE_SIZE`
+/// is too large to pad the record up to within [`MAX_PADDED_LEN`].
+pub(crate) fn save<F: NorFlash>(
+    flash: &mut F,
+    offset: u32,
+    config: PersistedConfig,
+) -> Result<(), PersistenceError<F::Error>> {
+    let record = config.to_bytes();
+    let padded_len = align_up(RECORD_LEN, F::WRITE_SIZE);
+    if padded_len > MAX_PADDED_LEN {
+        return Err(PersistenceError::UnsupportedWriteGranularity);
+    }
+
+    // Erased NOR flash reads as all-ones; pad with that rather than
+    // zero so an unwritten tail doesn't look like a (wrong) zero record.
+    let mut buf = [0xFFu8; MAX_PADDED_LEN];
+    buf[..RECORD_LEN].copy_from_slice(&record);
+
+    let erase_len = align_up(padded_len, F::ERASE_SIZE) as u32;
+    flash
+        .erase(offset, offset + erase_len)
+        .map_err(PersistenceError::Flash)?;
+    flash
+        .write(offset, &buf[..padded_len])
+        .map_err(PersistenceError::Flash)?;
+    Ok(())
+}
+
+/// Read and validate a [`PersistedConfig`] previously written by
+/// [`save`], rejecting a partially-written or wrong-version record.
+pub(crate) fn load<F: ReadNorFlash>(
+    flash: &mut F,
+    offset: u32,
+) -> Result<PersistedConfig, PersistenceError<F::Error>> {
+    let mut buf = [0u8; RECORD_LEN];
+    flash
+        .read(offset, &mut buf)
+        .map_err(PersistenceError::Flash)?;
+    PersistedConfig::from_bytes(&buf)
+}
+
+fn align_up(len: usize, granularity: usize) -> usize {
+    if granularity == 0 {
+        len
+    } else {
+        len.div_ceil(granularity) * granularity
+    }
+}
+
+/// CRC-32 (IEEE 802.3), computed bit-by-bit since records are a handful
+/// of bytes and don't warrant a lookup table.
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_storage::nor_flash::{ErrorType, NorFlashError, NorFlashErrorKind};
+
+    const PAGE_SIZE: usize = 256;
+
+    struct MockFlash {
+        data: [u8; PAGE_SIZE],
+    }
+
+    #[derive(Debug)]
+    struct MockError(NorFlashErrorKind);
+
+    impl NorFlashError for MockError {
+        fn kind(&self) -> NorFlashErrorKind {
+            self.0
+        }
+    }
+
+    impl ErrorType for MockFlash {
+        type Error = MockError;
+    }
+
+    impl ReadNorFlash for MockFlash {
+        const READ_SIZE: usize = 1;
+
+        fn read(&mut self, offset: u32, bytes: &mut [u8]) -> Result<(), Self::Error> {
+            let offset = offset as usize;
+            bytes.copy_from_slice(&self.data[offset..offset + bytes.len()]);
+            Ok(())
+        }
+
+        fn capacity(&self) -> usize {
+            PAGE_SIZE
+        }
+    }
+
+    impl NorFlash for MockFlash {
+        const WRITE_SIZE: usize = 4;
+        const ERASE_SIZE: usize = 64;
+
+        fn erase(&mut self, from: u32, to: u32) -> Result<(), Self::Error> {
+            self.data[from as usize..to as usize].fill(0xFF);
+            Ok(())
+        }
+
+        fn write(&mut self, offset: u32, bytes: &[u8]) -> Result<(), Self::Error> {
+            let offset = offset as usize;
+            self.data[offset..offset + bytes.len()].copy_from_slice(bytes);
+            Ok(())
+        }
+    }
+
+    /// A flash whose write granularity is too coarse to pad `RECORD_LEN`
+    /// into within [`MAX_PADDED_LEN`].
+    struct WideWriteFlash {
+        data: [u8; PAGE_SIZE],
+    }
+
+    impl ErrorType for WideWriteFlash {
+        type Error = MockError;
+    }
+
+    impl ReadNorFlash for WideWriteFlash {
+        const READ_SIZE: usize = 1;
+
+        fn read(&mut self, offset: u32, bytes: &mut [u8]) -> Result<(), Self::Error> {
+            let offset = offset as usize;
+            bytes.copy_from_slice(&self.data[offset..offset + bytes.len()]);
+            Ok(())
+        }
+
+        fn capacity(&self) -> usize {
+            PAGE_SIZE
+        }
+    }
+
+    impl NorFlash for WideWriteFlash {
+        const WRITE_SIZE: usize = MAX_PADDED_LEN + 1;
+        const ERASE_SIZE: usize = 256;
+
+        fn erase(&mut self, from: u32, to: u32) -> Result<(), Self::Error> {
+            self.data[from as usize..to as usize].fill(0xFF);
+            Ok(())
+        }
+
+        fn write(&mut self, offset: u32, bytes: &[u8]) -> Result<(), Self::Error> {
+            let offset = offset as usize;
+            self.data[offset..offset + bytes.len()].copy_from_slice(bytes);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn round_trips_a_config() {
+        let mut flash = MockFlash {
+            data: [0xFF; PAGE_SIZE],
+        };
+        let config = PersistedConfig {
+            kp: 1.5,
+            ki: 0.25,
+            kd: 0.1,
+            output_limit: 10.0,
+            frequency_hz: 1000,
+        };
+
+        save(&mut flash, 0, config).unwrap();
+        let loaded = load(&mut flash, 0).unwrap();
+        assert_eq!(loaded, config);
+    }
+
+    #[test]
+    fn rejects_a_blank_page() {
+        let mut flash = MockFlash {
+            data: [0xFF; PAGE_SIZE],
+        };
+        let err = load(&mut flash, 0).unwrap_err();
+        assert!(matches!(err, PersistenceError::InvalidHeader));
+    }
+
+    #[test]
+    fn rejects_a_corrupted_record() {
+        let mut flash = MockFlash {
+            data: [0xFF; PAGE_SIZE],
+        };
+        let config = PersistedConfig {
+            kp: 1.0,
+            ki: 0.0,
+            kd: 0.0,
+            output_limit: 1.0,
+            frequency_hz: 500,
+        };
+        save(&mut flash, 0, config).unwrap();
+        flash.data[10] ^= 0xFF; // flip bits inside the kp field
+
+        let err = load(&mut flash, 0).unwrap_err();
+        assert!(matches!(err, PersistenceError::CrcMismatch));
+    }
+
+    #[test]
+    fn rejects_a_write_granularity_too_coarse_to_pad_into() {
+        let mut flash = WideWriteFlash {
+            data: [0xFF; PAGE_SIZE],
+        };
+        let config = PersistedConfig {
+            kp: 1.0,
+            ki: 0.0,
+            kd: 0.0,
+            output_limit: 1.0,
+            frequency_hz: 500,
+        };
+
+        let err = save(&mut flash, 0, config).unwrap_err();
+        assert!(matches!(err, PersistenceError::UnsupportedWriteGranularity));
+    }
+}
@@ -0,0 +1,105 @@
+//! PID controller for real-time control loops.
+
+/// PID Controller optimized for real-time control
+#[derive(Debug, Clone, Copy)]
+pub struct PidController {
+    kp: f64,
+    ki: f64,
+    kd: f64,
+    output_limit: f64,
+    integral: f64,
+    previous_error: f64,
+}
+
+impl PidController {
+    pub fn new(kp: f64, ki: f64, kd: f64, output_limit: f64) -> Self {
+        Self {
+            kp,
+            ki,
+            kd,
+            output_limit,
+            integral: 0.0,
+            previous_error: 0.0,
+        }
+    }
+
+    /// Compute PID output
+    pub fn compute(&mut self, setpoint: f64, measured: f64, dt: f64) -> f64 {
+        let error = setpoint - measured;
+        self.integral += error * dt;
+
+        // Anti-windup
+        self.integral = self.integral.clamp(-self.output_limit, self.output_limit);
+
+        let derivative = if dt > 0.0 {
+            (error - self.previous_error) / dt
+        } else {
+            0.0
+        };
+
+        self.previous_error = error;
+
+        let output = self.kp * error + self.ki * self.integral + self.kd * derivative;
+        output.clamp(-self.output_limit, self.output_limit)
+    }
+
+    /// Reset controller state
+    pub fn reset(&mut self) {
+        self.integral = 0.0;
+        self.previous_error = 0.0;
+    }
+}
+
+#[cfg(feature = "persistence")]
+impl PidController {
+    /// Persist this controller's gains, together with `frequency_hz`, to
+    /// `flash` at `offset`. Erases and writes the covering region; does
+    /// not preserve the controller's runtime integral/derivative state.
+    pub fn save_to<F: embedded_storage::nor_flash::NorFlash>(
+        &self,
+        flash: &mut F,
+        offset: u32,
+        frequency_hz: u32,
+    ) -> Result<(), crate::persistence::PersistenceError<F::Error>> {
+        crate::persistence::save(
+            flash,
+            offset,
+            crate::persistence::PersistedConfig {
+                kp: self.kp,
+                ki: self.ki,
+                kd: self.kd,
+                output_limit: self.output_limit,
+                frequency_hz,
+            },
+        )
+    }
+
+    /// Load gains and loop frequency previously written by
+    /// [`PidController::save_to`], returning a freshly-reset controller
+    /// plus the stored frequency.
+    pub fn load_from<F: embedded_storage::nor_flash::ReadNorFlash>(
+        flash: &mut F,
+        offset: u32,
+    ) -> Result<(Self, u32), crate::persistence::PersistenceError<F::Error>> {
+        let config = crate::persistence::load(flash, offset)?;
+        Ok((
+            Self::new(config.kp, config.ki, config.kd, config.output_limit),
+            config.frequency_hz,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pid_controller() {
+        let mut pid = PidController::new(1.0, 0.1, 0.05, 10.0);
+
+        // Test setpoint tracking
+        let output = pid.compute(5.0, 0.0, 0.01);
+        assert!(output > 0.0, "Output should be positive for positive error");
+        assert!(output <= 10.0, "Output should respect limits");
+    }
+}
@@ -0,0 +1,73 @@
+//! Sensor data buffer with a lock-free validity check.
+
+use core::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+use crate::lock::Guarded;
+
+/// Sensor data buffer for real-time access.
+///
+/// Writers and readers synchronize through a small critical section (or
+/// `parking_lot` on `std`); the timestamp and validity flag are plain
+/// atomics so `is_valid` never has to take the lock.
+pub struct SensorBuffer<T: Copy> {
+    data: Guarded<T>,
+    timestamp: AtomicU64,
+    valid: AtomicBool,
+}
+
+impl<T: Copy + Default> SensorBuffer<T> {
+    pub fn new() -> Self {
+        Self {
+            data: Guarded::new(T::default()),
+            timestamp: AtomicU64::new(0),
+            valid: AtomicBool::new(false),
+        }
+    }
+
+    /// Write sensor data (writer side)
+    pub fn write(&self, data: T, timestamp_us: u64) {
+        self.data.with(|slot| *slot = data);
+        self.timestamp.store(timestamp_us, Ordering::Release);
+        self.valid.store(true, Ordering::Release);
+    }
+
+    /// Read sensor data (reader side)
+    pub fn read(&self) -> Option<(T, u64)> {
+        if !self.valid.load(Ordering::Acquire) {
+            return None;
+        }
+
+        let data = self.data.with(|slot| *slot);
+        let timestamp = self.timestamp.load(Ordering::Acquire);
+        Some((data, timestamp))
+    }
+
+    /// Check if data is available
+    pub fn is_valid(&self) -> bool {
+        self.valid.load(Ordering::Acquire)
+    }
+}
+
+impl<T: Copy + Default> Default for SensorBuffer<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sensor_buffer() {
+        let buffer = SensorBuffer::<f64>::new();
+        assert!(!buffer.is_valid());
+
+        buffer.write(42.0, 1000);
+        assert!(buffer.is_valid());
+
+        let (data, ts) = buffer.read().unwrap();
+        assert_eq!(data, 42.0);
+        assert_eq!(ts, 1000);
+    }
+}
@@ -0,0 +1,209 @@
+//! Real-time performance statistics.
+//!
+//! Latency is tracked with a fixed-size histogram rather than a running
+//! sum, so `percentile`/`jitter_us` can answer tail-latency questions in
+//! O(1) memory instead of the integer-division running mean losing
+//! precision over long runs.
+
+use crate::math::{ceil, sqrt};
+
+/// Default number of histogram buckets.
+pub const DEFAULT_HISTOGRAM_BUCKETS: usize = 64;
+
+/// Default histogram range in microseconds; latencies at or above this
+/// fall into the top bucket.
+pub const DEFAULT_HISTOGRAM_RANGE_US: u64 = 10_000;
+
+/// Real-time statistics for performance monitoring.
+///
+/// `BUCKETS` sizes the latency histogram backing [`RtStats::percentile`];
+/// embedded users can shrink it to keep the table small.
+#[derive(Debug, Clone, Copy)]
+pub struct RtStats<const BUCKETS: usize = DEFAULT_HISTOGRAM_BUCKETS> {
+    pub min_latency_us: u64,
+    pub max_latency_us: u64,
+    pub avg_latency_us: u64,
+    pub cycles_count: u64,
+    pub deadline_misses: u64,
+    histogram: [u32; BUCKETS],
+    bucket_width_us: u64,
+    range_max_us: u64,
+    // Welford's online mean/variance, kept alongside the histogram so
+    // `avg_latency_us`/`jitter_us` stay precise over long runs.
+    mean: f64,
+    m2: f64,
+    /// Cumulative time spent busy-waiting in `RtTimer::wait_next_cycle`,
+    /// and the number of spin iterations that took. Only present with
+    /// the `tuning` feature, so the hot path has nothing to touch when
+    /// it's off.
+    #[cfg(feature = "tuning")]
+    pub busy_wait_us: u64,
+    #[cfg(feature = "tuning")]
+    pub spin_iterations: u64,
+}
+
+impl<const BUCKETS: usize> RtStats<BUCKETS> {
+    pub fn new() -> Self {
+        Self::with_range(DEFAULT_HISTOGRAM_RANGE_US)
+    }
+
+    /// Build stats with a histogram spanning `[0, range_max_us)`.
+    /// Latencies at or above `range_max_us` are clamped into the top
+    /// bucket.
+    pub fn with_range(range_max_us: u64) -> Self {
+        let buckets = BUCKETS.max(1) as u64;
+        let range_max_us = range_max_us.max(1);
+        Self {
+            min_latency_us: u64::MAX,
+            max_latency_us: 0,
+            avg_latency_us: 0,
+            cycles_count: 0,
+            deadline_misses: 0,
+            histogram: [0; BUCKETS],
+            bucket_width_us: (range_max_us / buckets).max(1),
+            range_max_us,
+            mean: 0.0,
+            m2: 0.0,
+            #[cfg(feature = "tuning")]
+            busy_wait_us: 0,
+            #[cfg(feature = "tuning")]
+            spin_iterations: 0,
+        }
+    }
+
+    pub fn update(&mut self, latency_us: u64, deadline_us: u64) {
+        self.min_latency_us = self.min_latency_us.min(latency_us);
+        self.max_latency_us = self.max_latency_us.max(latency_us);
+        self.cycles_count += 1;
+
+        // Welford's online update.
+        let delta = latency_us as f64 - self.mean;
+        self.mean += delta / self.cycles_count as f64;
+        let delta2 = latency_us as f64 - self.mean;
+        self.m2 += delta * delta2;
+        self.avg_latency_us = self.mean as u64;
+
+        if BUCKETS > 0 {
+            let clamped = latency_us.min(self.range_max_us - 1);
+            let bucket = ((clamped / self.bucket_width_us) as usize).min(BUCKETS - 1);
+            self.histogram[bucket] += 1;
+        }
+
+        if latency_us > deadline_us {
+            self.deadline_misses += 1;
+        }
+    }
+
+    /// Approximate latency at quantile `q` (e.g. `0.99` for p99), in
+    /// microseconds, derived from the histogram.
+    pub fn percentile(&self, q: f64) -> u64 {
+        if self.cycles_count == 0 || BUCKETS == 0 {
+            return 0;
+        }
+
+        let q = q.clamp(0.0, 1.0);
+        let target = (ceil(q * self.cycles_count as f64) as u64).max(1);
+        let mut cumulative = 0u64;
+        for (i, &count) in self.histogram.iter().enumerate() {
+            cumulative += count as u64;
+            if cumulative >= target {
+                return (i as u64 + 1) * self.bucket_width_us;
+            }
+        }
+        self.max_latency_us
+    }
+
+    /// Latency standard deviation ("jitter") in microseconds.
+    pub fn jitter_us(&self) -> u64 {
+        if self.cycles_count < 2 {
+            return 0;
+        }
+        let variance = self.m2 / self.cycles_count as f64;
+        sqrt(variance) as u64
+    }
+
+    /// Record time spent busy-waiting for one cycle. Only available with
+    /// the `tuning` feature.
+    #[cfg(feature = "tuning")]
+    pub fn record_busy_wait(&mut self, busy_wait_us: u64, spin_iterations: u64) {
+        self.busy_wait_us += busy_wait_us;
+        self.spin_iterations += spin_iterations;
+    }
+
+    /// Fraction of cumulative cycle latency spent busy-waiting, in
+    /// `[0, 1]`. Only available with the `tuning` feature.
+    #[cfg(feature = "tuning")]
+    pub fn parked_ratio(&self) -> f64 {
+        let total_latency_us = self.mean * self.cycles_count as f64;
+        if total_latency_us <= 0.0 {
+            0.0
+        } else {
+            self.busy_wait_us as f64 / total_latency_us
+        }
+    }
+}
+
+impl<const BUCKETS: usize> Default for RtStats<BUCKETS> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rt_stats() {
+        let mut stats: RtStats = RtStats::new();
+
+        stats.update(100, 1000);
+        assert_eq!(stats.cycles_count, 1);
+        assert_eq!(stats.deadline_misses, 0);
+
+        stats.update(2000, 1000);
+        assert_eq!(stats.cycles_count, 2);
+        assert_eq!(stats.deadline_misses, 1);
+    }
+
+    #[test]
+    fn test_percentile_tracks_tail_latency() {
+        let mut stats: RtStats<16> = RtStats::with_range(1000);
+        for latency in 1..=100u64 {
+            stats.update(latency, 1000);
+        }
+
+        let p50 = stats.percentile(0.5);
+        let p99 = stats.percentile(0.99);
+        assert!(p50 < p99, "p99 should be larger than p50");
+        assert!(p99 <= 100 + (1000 / 16));
+    }
+
+    #[test]
+    fn test_jitter_is_zero_for_constant_latency() {
+        let mut stats: RtStats = RtStats::new();
+        for _ in 0..10 {
+            stats.update(500, 1000);
+        }
+        assert_eq!(stats.jitter_us(), 0);
+    }
+
+    #[cfg(feature = "tuning")]
+    #[test]
+    fn test_record_busy_wait_tracks_parked_ratio() {
+        let mut stats: RtStats = RtStats::new();
+        stats.update(1000, 1000);
+        assert_eq!(stats.parked_ratio(), 0.0, "no busy-wait recorded yet");
+
+        stats.record_busy_wait(250, 3);
+        assert_eq!(stats.busy_wait_us, 250);
+        assert_eq!(stats.spin_iterations, 3);
+        assert_eq!(stats.parked_ratio(), 0.25);
+
+        stats.update(1000, 1000);
+        stats.record_busy_wait(250, 3);
+        assert_eq!(stats.busy_wait_us, 500);
+        assert_eq!(stats.spin_iterations, 6);
+        assert_eq!(stats.parked_ratio(), 0.25);
+    }
+}
@@ -0,0 +1,170 @@
+//! High-resolution, [`Clock`]-driven timer for real-time control.
+
+use crate::diagnostics::{CycleSample, DeadlineMiss, Diagnostics, RingIter};
+use crate::error::{Layer0Error, Result};
+use crate::lock::Guarded;
+use crate::stats::{RtStats, DEFAULT_HISTOGRAM_BUCKETS};
+use crate::Clock;
+
+#[cfg(feature = "std")]
+use crate::clock::StdClock;
+
+/// Default depth of the recent-cycle and recent-miss diagnostics rings.
+pub const DEFAULT_DIAGNOSTICS_DEPTH: usize = 8;
+
+/// High-resolution timer for real-time control.
+///
+/// Generic over a [`Clock`] so the same cycle-pacing logic drives a
+/// control loop whether `C` is backed by `std::time::Instant` or a
+/// hardware tick counter on bare metal. `N` sizes the rolling diagnostics
+/// window kept by [`RtTimer::recent_samples`] and
+/// [`RtTimer::recent_misses`]; `BUCKETS` sizes the latency histogram
+/// backing [`RtTimer::get_stats`]. Most callers can leave both at their
+/// defaults.
+pub struct RtTimer<C: Clock, const N: usize = DEFAULT_DIAGNOSTICS_DEPTH, const BUCKETS: usize = DEFAULT_HISTOGRAM_BUCKETS> {
+    clock: C,
+    start_ticks: u64,
+    period_ticks: u64,
+    cycle_index: u64,
+    stats: Guarded<RtStats<BUCKETS>>,
+    diagnostics: Guarded<Diagnostics<N>>,
+}
+
+impl<C: Clock, const N: usize, const BUCKETS: usize> RtTimer<C, N, BUCKETS> {
+    /// Build a timer running at `frequency_hz`, driven by `clock`.
+    pub fn with_clock(frequency_hz: u32, clock: C) -> Self {
+        let period_ticks = (C::TICK_HZ / frequency_hz as u64).max(1);
+        let start_ticks = clock.now_ticks();
+        Self {
+            clock,
+            start_ticks,
+            period_ticks,
+            cycle_index: 0,
+            stats: Guarded::new(RtStats::new()),
+            diagnostics: Guarded::new(Diagnostics::new()),
+        }
+    }
+
+    fn ticks_to_us(&self, ticks: u64) -> u64 {
+        // `ticks` is cumulative since this timer started, so multiplying
+        // before dividing in u64 overflows after a few hours at
+        // nanosecond tick rates (e.g. StdClock). Widen to u128 for the
+        // multiply instead.
+        (ticks as u128 * 1_000_000 / C::TICK_HZ as u128) as u64
+    }
+
+    /// Wait for next cycle (busy-wait for precision)
+    pub fn wait_next_cycle(&mut self) -> Result<()> {
+        let cycle_start = self.clock.now_ticks();
+        let elapsed = cycle_start - self.start_ticks;
+
+        // Calculate next cycle boundary
+        let remainder = elapsed % self.period_ticks;
+        let next_cycle_ticks = self.period_ticks - remainder;
+        let target = cycle_start + next_cycle_ticks;
+
+        // Busy-wait for the remaining time (more accurate than sleep)
+        #[cfg(feature = "tuning")]
+        let busy_wait_start = self.clock.now_ticks();
+        #[cfg(feature = "tuning")]
+        let mut spin_iterations: u64 = 0;
+
+        while self.clock.now_ticks() < target {
+            core::hint::spin_loop();
+            #[cfg(feature = "tuning")]
+            {
+                spin_iterations += 1;
+            }
+        }
+
+        let now_ticks = self.clock.now_ticks();
+        let actual_delay_ticks = now_ticks - cycle_start;
+        let latency_us = self.ticks_to_us(actual_delay_ticks);
+        let deadline_us = self.ticks_to_us(self.period_ticks);
+
+        let cycle_index = self.cycle_index;
+        self.cycle_index += 1;
+
+        self.stats.with(|s| s.update(latency_us, deadline_us));
+        #[cfg(feature = "tuning")]
+        {
+            let busy_wait_us = self.ticks_to_us(now_ticks - busy_wait_start);
+            self.stats
+                .with(|s| s.record_busy_wait(busy_wait_us, spin_iterations));
+        }
+        self.diagnostics
+            .with(|d| d.record_cycle(cycle_index, latency_us, deadline_us));
+
+        if latency_us > deadline_us {
+            return Err(Layer0Error::RealTimeViolation {
+                expected_us: deadline_us,
+                actual_us: latency_us,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Get performance statistics
+    pub fn get_stats(&self) -> RtStats<BUCKETS> {
+        self.stats.with(|s| *s)
+    }
+
+    /// Microseconds elapsed since this timer was constructed.
+    pub fn elapsed_us(&self) -> u64 {
+        self.ticks_to_us(self.clock.now_ticks() - self.start_ticks)
+    }
+
+    /// The last `N` cycle snapshots, oldest first.
+    pub fn recent_samples(&self) -> RingIter<CycleSample, N> {
+        self.diagnostics.with(|d| d.recent_samples())
+    }
+
+    /// The last `N` deadline-miss events, oldest first.
+    pub fn recent_misses(&self) -> RingIter<DeadlineMiss, N> {
+        self.diagnostics.with(|d| d.recent_misses())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<const N: usize, const BUCKETS: usize> RtTimer<StdClock, N, BUCKETS> {
+    pub fn new(frequency_hz: u32) -> Self {
+        Self::with_clock(frequency_hz, StdClock)
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timer_runs_a_cycle() {
+        let mut timer: RtTimer<StdClock> = RtTimer::new(1000);
+        timer.wait_next_cycle().ok();
+        assert_eq!(timer.get_stats().cycles_count, 1);
+        assert_eq!(timer.recent_samples().count(), 1);
+    }
+
+    #[test]
+    fn test_ticks_to_us_does_not_overflow_after_hours_of_uptime() {
+        let timer: RtTimer<StdClock> = RtTimer::new(1000);
+        let six_hours_of_ticks = 6 * 3600 * StdClock::TICK_HZ;
+        assert_eq!(
+            timer.ticks_to_us(six_hours_of_ticks),
+            6 * 3600 * 1_000_000
+        );
+    }
+
+    #[cfg(feature = "tuning")]
+    #[test]
+    fn test_timer_accounts_busy_wait_time() {
+        let mut timer: RtTimer<StdClock> = RtTimer::new(1000);
+        timer.wait_next_cycle().ok();
+        timer.wait_next_cycle().ok();
+
+        let stats = timer.get_stats();
+        assert!(stats.spin_iterations > 0, "spin loop should have run at least once");
+        assert!(stats.busy_wait_us > 0, "busy-wait time should accumulate");
+        assert!(stats.parked_ratio() > 0.0, "parked_ratio should be nonzero once busy-wait time is recorded");
+    }
+}